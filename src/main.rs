@@ -1,135 +1,550 @@
-use std::time::{Instant, Duration};
+use std::time::{Duration, Instant as StdInstant, SystemTime};
 use std::thread::sleep;
+use std::fmt;
+
+/// Errors produced by [`Stopwatch`] operations.
+///
+/// These replace the old `-1.0` sentinel return values so callers can
+/// distinguish a genuine elapsed time of zero from a misuse of the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// `start` was called while the stopwatch was already running.
+    AlreadyStarted,
+    /// `stop`/`lap` was called while the stopwatch was not running.
+    AlreadyStopped,
+    /// `get_lap` was called with an index outside the recorded laps.
+    InvalidLap(usize),
+    /// `unpause` was called while the stopwatch was not paused.
+    NotPaused,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AlreadyStarted => write!(f, "stopwatch is already started"),
+            Error::AlreadyStopped => write!(f, "stopwatch is already stopped"),
+            Error::InvalidLap(index) => write!(f, "no lap recorded at index {}", index),
+            Error::NotPaused => write!(f, "stopwatch is not paused"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A pluggable timepoint source for [`Stopwatch`].
+///
+/// Implementing this over another clock (a simulated clock for tests, a
+/// tick counter) makes `Stopwatch`'s elapsed-time measurement use that
+/// clock instead of `std::time::Instant`. The `*_moments` records are still
+/// taken from `std::time::SystemTime::now()`, so `Stopwatch` still needs
+/// std's system clock.
+pub trait Instant: Copy + PartialEq + fmt::Debug {
+    /// Returns the current timepoint.
+    fn now() -> Self;
+    /// Returns the duration since `earlier`, clamped to zero if `earlier`
+    /// is actually later (mirrors `std::time::Instant::saturating_duration_since`).
+    fn saturating_duration_since(&self, earlier: Self) -> Duration;
+}
+
+impl Instant for StdInstant {
+    fn now() -> Self {
+        StdInstant::now()
+    }
+
+    fn saturating_duration_since(&self, earlier: Self) -> Duration {
+        StdInstant::saturating_duration_since(self, earlier)
+    }
+}
+
+/// The running state of a [`Stopwatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunState<I: Instant> {
+    /// No interval is open; `elapsed`/`laps` hold only completed intervals.
+    Stopped,
+    /// An interval is open and accumulating time from the stored instant.
+    Running(I),
+    /// An interval was open but is frozen; `pending` holds its time so far.
+    Paused,
+}
+
+/// A cloneable snapshot of a [`Stopwatch`]'s full start/pause/lap timeline,
+/// suitable for rendering a history UI without holding a borrow on the
+/// stopwatch itself.
+#[derive(Debug, Clone)]
+pub struct StopwatchData {
+    /// Total elapsed time across all completed and in-progress intervals.
+    pub elapsed: Duration,
+    /// Elapsed time of the lap currently in progress.
+    pub lap_elapsed: Duration,
+    /// Wall-clock moments at which the watch was started or resumed.
+    pub start_moments: Vec<SystemTime>,
+    /// Wall-clock moments at which the watch was paused.
+    pub pause_moments: Vec<SystemTime>,
+    /// Wall-clock moments at which `lap` recorded a lap boundary. A lap
+    /// closed by `stop` has no entry here.
+    pub lap_moments: Vec<SystemTime>,
+    /// Durations of the completed laps.
+    pub laps: Vec<Duration>,
+}
 
 /// Stopwatch definition
-struct Stopwatch { start_point: Instant
-    , interval_start: f64
-    , lap_start: f64
+///
+/// Generic over its timepoint source `I`; [`SystemStopwatch`] is a type
+/// alias for the common case of using `std::time::Instant`.
+///
+/// `elapsed`, `pending` and `laps` are stored as [`Duration`] rather than
+/// `f64` seconds so repeated lap/pause folding doesn't accumulate float
+/// drift; the `f64`-returning methods convert on the way out. `I` tracks
+/// monotonic elapsed time, while the `*_moments` fields separately record
+/// wall-clock `SystemTime`s for each start/pause/lap event.
+pub struct Stopwatch<I: Instant = StdInstant> { start_point: I
+    , state: RunState<I>
+    , pending: Duration
     , end: f64
-    , elapsed: f64
-    , laps:Vec<f64>
+    , elapsed: Duration
+    , laps:Vec<Duration>
+    , start_moments: Vec<SystemTime>
+    , pause_moments: Vec<SystemTime>
+    , lap_moments: Vec<SystemTime>
 }
 
+/// A [`Stopwatch`] driven by the operating system clock, `std::time::Instant`.
+pub type SystemStopwatch = Stopwatch<StdInstant>;
+
 ///Stopwatch implementation
-impl Stopwatch {
-  fn new() -> Stopwatch {
-    let ts = Instant::now();
+impl<I: Instant> Stopwatch<I> {
+  fn new() -> Stopwatch<I> {
+    Stopwatch::with_elapsed(Duration::ZERO)
+  }
+
+  /// Creates a stopped `Stopwatch` whose elapsed accumulator is seeded with
+  /// `elapsed`, e.g. to resume a measurement carried over from elsewhere.
+  pub fn with_elapsed(elapsed: Duration) -> Stopwatch<I> {
+    let ts = I::now();
 
     Stopwatch { start_point: ts
-        , interval_start: 0.0
-        , lap_start: 0.0
+        , state: RunState::Stopped
+        , pending: Duration::ZERO
         , end:3.0
-        , elapsed:0.0
+        , elapsed
         , laps: Vec::new()
+        , start_moments: Vec::new()
+        , pause_moments: Vec::new()
+        , lap_moments: Vec::new()
     }
   }
 
   //Returns a timepoint as a float
   fn get_timepoint(&self) -> f64 {
-    let now = Instant::now();
-    let duration = now.duration_since(self.start_point);
+    let now = I::now();
+    let duration = now.saturating_duration_since(self.start_point);
 
     let secs = duration.as_secs() as f64;
     let nsecs = duration.subsec_nanos() as f64 / 1.0e9;
 
-    let tp = secs + nsecs;
-
-    return tp;
+    secs + nsecs
   }
 
   /// starts the stopwatch timer
-  /// return: -1 value if the clock is already running or new start point
+  /// return: Err(Error::AlreadyStarted) if the clock is already running,
+  /// otherwise the current start point
   ///
   /// Starts a new timer interval in the stopwatch. If the sotpwatch is active,
   /// elapsed time is incremented and a new interval is started.
-  pub fn start(&mut self) -> f64 {
+  pub fn start(&mut self) -> Result<f64, Error> {
     let tp = self.get_timepoint();
     self.end = tp;
 
-    //If we're already running, return -1.0
-    if self.interval_start != 0.0 {
-      //SDF generate an error?
-      return -1.0;
+    //If we're already running or paused, report an error
+    if self.state != RunState::Stopped {
+      return Err(Error::AlreadyStarted);
     }
 
-    //Not running so set the interval_start and lap_start to the current 
-    //timepoint
-    self.interval_start = tp;
+    //Not running so open a new interval at the current instant
+    self.state = RunState::Running(I::now());
+    self.start_moments.push(SystemTime::now());
 
     //Return the current start point
-    return tp;
+    Ok(tp)
   }
 
   /// Ends the current time interval which effectively stops the timer.
-  pub fn stop(&mut self)->f64 {
+  ///
+  /// Works whether the watch is currently running or paused.
+  pub fn stop(&mut self) -> Result<f64, Error> {
       //Get the current timepoint
       let tp = self.get_timepoint();
       self.end = tp;
 
-      //Check if we're already stopped
-      if self.interval_start == 0.0 {
-        //SDF generate an error?
-        return -1.0;
-      }
-
-      //Update elapsed time
-      let interval = tp - self.interval_start;
-      self.elapsed = self.elapsed + interval;
+      //Fold whatever time remains in the open interval into this lap
+      let interval = match self.state {
+        RunState::Stopped => return Err(Error::AlreadyStopped),
+        RunState::Running(started) => {
+          let since_start = I::now().saturating_duration_since(started);
+          self.elapsed += since_start;
+          self.pending + since_start
+        }
+        RunState::Paused => self.pending,
+      };
 
       //Update lap
       self.laps.push(interval);
 
-      //Clear lap_start and interval_start variables
-      self.interval_start = 0.0;
-      self.lap_start = 0.0;
-      return self.elapsed;
+      //Clear pending and close the interval
+      self.pending = Duration::ZERO;
+      self.state = RunState::Stopped;
+      Ok(self.elapsed.as_secs_f64())
   }
 
   //Function to stop one lap and start the next one
-  pub fn lap( &mut self ) -> f64 {
+  pub fn lap( &mut self ) -> Result<f64, Error> {
     //Get the current timepoint
     let tp = self.get_timepoint();
     self.end = tp;
 
-    //Check if we're already stopped
-    if self.interval_start == 0.0 {
-      //SDF generate an error?
-      return -1.0;
-    }
+    //A lap boundary only makes sense while actively running
+    let started = match self.state {
+      RunState::Running(started) => started,
+      RunState::Paused | RunState::Stopped => return Err(Error::AlreadyStopped),
+    };
 
-    let interval = tp - self.interval_start;
-    self.elapsed = self.elapsed + interval;
+    let now = I::now();
+    let since_start = now.saturating_duration_since(started);
+    let interval = self.pending + since_start;
+    self.elapsed += since_start;
+    self.pending = Duration::ZERO;
 
     self.laps.push(interval);
+    self.lap_moments.push(SystemTime::now());
 
-    self.interval_start = tp;
+    self.state = RunState::Running(now);
 
-    return interval;
+    Ok(interval.as_secs_f64())
   }
 
-  //Returns the time for a specific lap
-  pub fn get_lap(&mut self, index: usize ) -> f64 {
-      if index < self.laps.len() {
-        return self.laps[index];
+  /// Freezes the current interval without ending the measurement, folding
+  /// the time accrued so far into `elapsed` so `unpause` can continue it.
+  pub fn pause(&mut self) -> Result<(), Error> {
+    let started = match self.state {
+      RunState::Running(started) => started,
+      RunState::Paused | RunState::Stopped => return Err(Error::AlreadyStopped),
+    };
+
+    let since_start = I::now().saturating_duration_since(started);
+    self.elapsed += since_start;
+    self.pending += since_start;
+    self.state = RunState::Paused;
+    self.pause_moments.push(SystemTime::now());
+
+    Ok(())
+  }
+
+  /// Resumes a paused interval from where `pause` froze it.
+  pub fn unpause(&mut self) -> Result<(), Error> {
+    if self.state != RunState::Paused {
+      return Err(Error::NotPaused);
+    }
+
+    self.state = RunState::Running(I::now());
+    self.start_moments.push(SystemTime::now());
+
+    Ok(())
+  }
+
+  /// Returns whether the stopwatch is currently paused.
+  pub fn is_paused(&self) -> bool {
+    self.state == RunState::Paused
+  }
+
+  /// Returns the accumulated elapsed time, including the open interval if
+  /// the watch is running, or the time accrued so far if it is paused.
+  ///
+  /// This and [`Stopwatch::elapsed_duration`] are the `f64`/`Duration`
+  /// conversion pair for this crate: a plain `impl From<Duration> for f32`/
+  /// `impl From<f32> for Duration`, as in trezor's `time.rs`, isn't
+  /// possible here because both types are foreign to this crate and the
+  /// orphan rules forbid implementing a foreign trait for a foreign type
+  /// (`E0117`). `Duration::as_secs_f64` (used throughout this module) and
+  /// `Duration::from_secs_f64` are std's own equivalent of that convenience
+  /// conversion.
+  pub fn elapsed(&self) -> f64 {
+    self.elapsed_duration().as_secs_f64()
+  }
+
+  /// Returns the accumulated elapsed time as a [`Duration`], with the same
+  /// live behavior as [`Stopwatch::elapsed`].
+  pub fn elapsed_duration(&self) -> Duration {
+    match self.state {
+      RunState::Running(started) => {
+        self.elapsed + I::now().saturating_duration_since(started)
       }
+      RunState::Paused | RunState::Stopped => self.elapsed,
+    }
+  }
+
+  /// Resets `elapsed` and `laps` and immediately starts a fresh interval.
+  pub fn restart(&mut self) {
+    self.elapsed = Duration::ZERO;
+    self.pending = Duration::ZERO;
+    self.laps.clear();
+    self.start_moments.clear();
+    self.pause_moments.clear();
+    self.lap_moments.clear();
+    self.state = RunState::Running(I::now());
+    self.start_moments.push(SystemTime::now());
+  }
 
-      return -1.0;
+  //Returns the time for a specific lap
+  pub fn get_lap(&mut self, index: usize ) -> Result<f64, Error> {
+      self.lap_duration(index).map(|d| d.as_secs_f64()).ok_or(Error::InvalidLap(index))
+  }
+
+  /// Returns the recorded duration of a specific lap, as a [`Duration`]
+  /// rather than `f64` seconds.
+  pub fn lap_duration(&self, index: usize) -> Option<Duration> {
+    self.laps.get(index).copied()
   }
 
   pub fn get_lap_count( &mut self  ) -> usize {
-    return self.laps.len();
+    self.laps.len()
+  }
+
+  /// Returns the elapsed time of the lap currently in progress, i.e. the
+  /// running time since the last lap boundary or start, not counting time
+  /// spent paused. Returns zero once the watch is stopped.
+  pub fn current_lap_elapsed(&self) -> Duration {
+    match self.state {
+      RunState::Running(started) => self.pending + I::now().saturating_duration_since(started),
+      RunState::Paused | RunState::Stopped => self.pending,
+    }
+  }
+
+  /// Returns a cloneable snapshot of the full start/pause/lap timeline,
+  /// for reconstructing a history UI without holding a borrow on `self`.
+  pub fn snapshot(&self) -> StopwatchData {
+    StopwatchData {
+      elapsed: self.elapsed_duration(),
+      lap_elapsed: self.current_lap_elapsed(),
+      start_moments: self.start_moments.clone(),
+      pause_moments: self.pause_moments.clone(),
+      lap_moments: self.lap_moments.clone(),
+      laps: self.laps.clone(),
+    }
+  }
+
+  /// Starts the watch and returns a [`Guard`] that stops it automatically
+  /// when dropped, so a scope can be timed without a paired `stop()` call:
+  ///
+  /// ```ignore
+  /// let _g = sw.guard()?;
+  /// do_work();
+  /// // sw is stopped here, even if do_work() panics or returns early.
+  /// ```
+  pub fn guard(&mut self) -> Result<Guard<'_, I>, Error> {
+    self.start()?;
+    Ok(Guard { stopwatch: self })
   }
 }
 
+/// An RAII guard returned by [`Stopwatch::guard`] that stops the stopwatch
+/// it borrows when dropped, folding the timed interval in exactly once
+/// even if the scope unwinds via a panic.
+pub struct Guard<'a, I: Instant = StdInstant> {
+    stopwatch: &'a mut Stopwatch<I>,
+}
+
+impl<'a, I: Instant> Drop for Guard<'a, I> {
+    fn drop(&mut self) {
+        let _ = self.stopwatch.stop();
+    }
+}
+
+/// Whether a [`Timer`] finishes once or restarts itself every period.
+///
+/// Modeled as an explicit enum rather than a `repeating: bool` flag so call
+/// sites read `TimerMode::Repeating` instead of an opaque `true`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    /// The timer finishes once and then stays finished.
+    Once,
+    /// The timer wraps back to zero every time it finishes.
+    Repeating,
+}
+
+/// A countdown/interval timer advanced by an externally supplied `delta`,
+/// as opposed to [`Stopwatch`] which measures wall-clock time itself.
+pub struct Timer {
+    duration: Duration,
+    mode: TimerMode,
+    elapsed: Duration,
+    finished: bool,
+    just_finished: bool,
+    times_finished_this_tick: u64,
+}
+
+impl Timer {
+  /// Creates a new timer counting down `duration` in the given `mode`.
+  pub fn new(duration: Duration, mode: TimerMode) -> Timer {
+    Timer { duration
+        , mode
+        , elapsed: Duration::ZERO
+        , finished: false
+        , just_finished: false
+        , times_finished_this_tick: 0
+    }
+  }
+
+  /// Advances the timer by `delta`, wrapping around `duration` as many
+  /// times as `delta` spans when in `TimerMode::Repeating`.
+  pub fn tick(&mut self, delta: Duration) -> &mut Timer {
+    //A finished Once timer stays finished and reports no further completions
+    if self.mode == TimerMode::Once && self.finished {
+      self.just_finished = false;
+      self.times_finished_this_tick = 0;
+      return self;
+    }
+
+    self.elapsed += delta;
+    self.just_finished = false;
+    self.times_finished_this_tick = 0;
+
+    if self.elapsed >= self.duration {
+      match self.mode {
+        TimerMode::Once => {
+          self.elapsed = self.duration;
+          self.times_finished_this_tick = 1;
+        }
+        TimerMode::Repeating => {
+          if self.duration.is_zero() {
+            self.times_finished_this_tick = 1;
+          } else {
+            let elapsed_nanos = self.elapsed.as_nanos();
+            let duration_nanos = self.duration.as_nanos();
+            self.times_finished_this_tick = (elapsed_nanos / duration_nanos)
+                .try_into()
+                .unwrap_or(u64::MAX);
+            self.elapsed = Duration::from_nanos((elapsed_nanos % duration_nanos) as u64);
+          }
+        }
+      }
+      self.finished = true;
+      self.just_finished = true;
+    }
+
+    self
+  }
+
+  /// Returns whether the timer has completed at least one period.
+  pub fn finished(&self) -> bool {
+    self.finished
+  }
+
+  /// Returns whether the timer crossed a period boundary on the most
+  /// recent `tick` call, even if that tick completed more than one period.
+  pub fn just_finished(&self) -> bool {
+    self.just_finished
+  }
+
+  /// Returns how many full periods were completed on the most recent
+  /// `tick` call (always `0` or `1` for `TimerMode::Once`). Widened to
+  /// `u64` because a single large `tick` can span far more periods than
+  /// `u32` can hold (e.g. a 1ns period ticked by 5 real seconds).
+  pub fn times_finished(&self) -> u64 {
+    self.times_finished_this_tick
+  }
+
+  /// Returns the time elapsed in the current period.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns the time remaining in the current period.
+  pub fn remaining(&self) -> Duration {
+    self.duration.saturating_sub(self.elapsed)
+  }
+
+  /// Returns how far through the current period the timer is, from `0.0`
+  /// to `1.0`.
+  pub fn percent(&self) -> f32 {
+    if self.duration.is_zero() {
+      1.0
+    } else {
+      self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+  }
+
+  /// Resets the timer to its initial, unfinished state without changing
+  /// its `duration` or `mode`.
+  pub fn reset(&mut self) {
+    self.elapsed = Duration::ZERO;
+    self.finished = false;
+    self.just_finished = false;
+    self.times_finished_this_tick = 0;
+  }
+
+  /// Returns the timer's configured period.
+  pub fn duration(&self) -> Duration {
+    self.duration
+  }
+
+  /// Returns the timer's mode.
+  pub fn mode(&self) -> TimerMode {
+    self.mode
+  }
+}
+
+
+/// Time to learn rust
+///
+fn main() {
+    let mut sw = SystemStopwatch::new();
+
+    let delay = Duration::new(1, 0 );
+
+    sw.start().unwrap();
+
+    if true {
+      sleep( delay);
+    }
+
+    let elapsed = sw.stop().unwrap();
+
+    println!("Runtime: {}", elapsed);
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  thread_local! {
+    //Millisecond tick count backing FakeInstant, advanced manually by tests
+    static FAKE_CLOCK_MILLIS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+  }
+
+  /// A deterministic, manually-advanced stand-in for `std::time::Instant`,
+  /// so timing tests don't need fragile `sleep`-based tolerance windows.
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  struct FakeInstant(u64);
+
+  impl Instant for FakeInstant {
+    fn now() -> Self {
+      FAKE_CLOCK_MILLIS.with(|millis| FakeInstant(millis.get()))
+    }
+
+    fn saturating_duration_since(&self, earlier: Self) -> Duration {
+      Duration::from_millis(self.0.saturating_sub(earlier.0))
+    }
+  }
+
+  fn advance_fake_clock(millis: u64) {
+    FAKE_CLOCK_MILLIS.with(|ticks| ticks.set(ticks.get() + millis));
+  }
+
   #[test]
   fn test_start_stop() {
-    let mut sw = Stopwatch::new(); 
-    sw.start();
-    let elapsed = sw.stop();
+    let mut sw = SystemStopwatch::new();
+    sw.start().unwrap();
+    let elapsed = sw.stop().unwrap();
 
     //This is the maximum allowed time for sequential start/stops
     let max=0.000001;
@@ -138,112 +553,289 @@ mod tests {
     assert!(elapsed < max, "ERROR: elapsed time of {} > {}", elapsed, max);
   }
 
-  #[test] 
-  //This test validates the start/stop timing with a given delay
+  #[test]
+  //This test validates the start/stop timing with a given delay, driven by
+  //FakeInstant so it's exact instead of a sleep-based tolerance window
   fn test_start_stop_delay() {
-    let delay_time = 1;
-    let delay = Duration::new(delay_time, 0);
-    let max=delay_time as f64 + 0.0003;
-    let min=delay_time as f64 - 0.0003;
+    let delay_time = 1.0;
 
-    let mut sw = Stopwatch::new(); 
-    sw.start();
-    sleep(delay);
-    let elapsed = sw.stop();
-    assert!(elapsed > 0.0, "ERROR: elapsed time of {} <= 0.0", elapsed);
-    assert!(elapsed < max, "ERROR: elapsed time of {} > max time of {}", elapsed, max);
-    assert!(elapsed > min, "ERROR: elapsed time of {} < min time of {}", elapsed, min);
+    let mut sw = Stopwatch::<FakeInstant>::new();
+    sw.start().unwrap();
+    advance_fake_clock(1000);
+    let elapsed = sw.stop().unwrap();
+    assert!(elapsed == delay_time, "ERROR: elapsed time of {} != {}", elapsed, delay_time);
   }
 
-  #[test] 
-  //This test validates the start/stop timing with a given delay
+  #[test]
+  //This test validates the start/stop timing across two separate intervals,
+  //driven by FakeInstant so it's exact instead of a sleep-based tolerance window
   fn test_start_stop_delay_twice() {
     let delay_time = 0.5;
-    let range = 0.0006;
 
-    let delay = Duration::new(0, (1.0e9*delay_time) as u32);
-    let max = 2.0 * delay_time + range;
-    let min = 2.0 * delay_time - range;
+    let mut sw = Stopwatch::<FakeInstant>::new();
+    sw.start().unwrap();
+    advance_fake_clock(500);
+    sw.stop().unwrap();
+    advance_fake_clock(500);
+    sw.start().unwrap();
+    advance_fake_clock(500);
+    let elapsed = sw.stop().unwrap();
 
-    println!("delay: {}, MIN: {}, MAX: {}",delay_time, min, max);
-
-    let mut sw = Stopwatch::new(); 
-    sw.start();
-    sleep(delay);
-    sw.stop();
-    sleep(delay);
-    sw.start();
-    sleep(delay);
-    let elapsed = sw.stop();
-
-    assert!(elapsed > 0.0, "ERROR: elapsed time of {} <= 0.0", elapsed);
-    assert!(elapsed < max, "ERROR: elapsed time of {} > max time of {}", elapsed, max);
-    assert!(elapsed > min, "ERROR: elapsed time of {} < min time of {}", elapsed, min );
+    assert!(elapsed == 2.0 * delay_time, "ERROR: elapsed time of {} != {}", elapsed, 2.0 * delay_time);
   }
 
   #[test]
-  //Test lap function by creating 5 laps at regular intervales
+  //Test lap function by creating 10 laps at regular intervals, driven by
+  //FakeInstant so it's exact instead of a sleep-based tolerance window
   fn test_lap() {
     let delay_time = 0.5;
-    let range = 0.0005;
     let laps = 10;
-    let min = delay_time - range;
-    let max = delay_time + range;
 
-    let delay = Duration::new(0, (1.0e9*delay_time) as u32);
-
-    let mut sw = Stopwatch::new(); 
-    sw.start();
+    let mut sw = Stopwatch::<FakeInstant>::new();
+    sw.start().unwrap();
 
     for _n in 0..laps {
-      sleep(delay);
-      sw.lap();
+      advance_fake_clock(500);
+      sw.lap().unwrap();
     }
 
     let mut total = 0.0;
     for i in 0..laps {
-      total = total + sw.get_lap(i);
+      total += sw.get_lap(i).unwrap();
     }
 
     let values = sw.get_lap_count();
     assert!( values == laps, "ERROR: Values {} does match laps {}", values, laps);
 
-    let avg = total as f64/ laps as f64;
-    assert!( avg > min, "ERROR: average {} less than min of {}", avg, min );
-    assert!( avg < max, "ERROR: average {} greater than max of {}", avg, max );
+    let avg = total / laps as f64;
+    assert!( avg == delay_time, "ERROR: average {} != {}", avg, delay_time);
   }
 
   #[test]
   //Test getLap with known invalid indices
   fn get_lap() {
-      let mut sw = Stopwatch::new();
+      let mut sw = SystemStopwatch::new();
 
-      let mut result = sw.get_lap(10);
-      assert!( result == -1.0, "ERROR Invalid lap did not result in -1 return");
+      let result = sw.get_lap(10);
+      assert!( result == Err(Error::InvalidLap(10)), "ERROR Invalid lap did not result in InvalidLap error");
 
       //Start twice
-      result = sw.start();
-      assert!(result > 0.0, "ERROR: start did not provide a positive timestamp");
-      result = sw.start();
-      assert!(result == -1.0, "ERROR: duplicate start did not fail {} != -1.0", result );
+      let first_start = sw.start();
+      assert!(first_start.unwrap() > 0.0, "ERROR: start did not provide a positive timestamp");
+      let second_start = sw.start();
+      assert!(second_start == Err(Error::AlreadyStarted), "ERROR: duplicate start did not fail {:?} != AlreadyStarted", second_start );
   }
-}
 
-/// Time to learn rust
-///
-fn main() {
-    let mut sw = Stopwatch::new(); 
+  #[test]
+  //Pausing should freeze the elapsed time until unpause resumes it
+  //driven by FakeInstant so it's exact instead of a sleep-based tolerance window
+  fn test_pause_unpause() {
+    let delay_time = 0.2;
+
+    let mut sw = Stopwatch::<FakeInstant>::new();
+    sw.start().unwrap();
+    advance_fake_clock(200);
+
+    sw.pause().unwrap();
+    assert!(sw.is_paused(), "ERROR: stopwatch did not report paused after pause()");
+    let frozen = sw.elapsed();
+
+    advance_fake_clock(200);
+    let still_frozen = sw.elapsed();
+    assert!(still_frozen == frozen, "ERROR: elapsed advanced while paused: {} -> {}", frozen, still_frozen);
+
+    sw.unpause().unwrap();
+    assert!(!sw.is_paused(), "ERROR: stopwatch still reports paused after unpause()");
+    advance_fake_clock(200);
+
+    let elapsed = sw.stop().unwrap();
+    let expected = 2.0 * delay_time;
+    assert!(elapsed == expected, "ERROR: elapsed {} != {}", elapsed, expected);
+  }
 
-    let delay = Duration::new(1, 0 );
-    
-    sw.start();
+  #[test]
+  //pause/unpause should reject calls made from the wrong state, and restart
+  //should clear the recorded laps
+  fn test_pause_errors() {
+    let mut sw = SystemStopwatch::new();
 
-    if true {
-      sleep( delay);
+    let pause_while_stopped = sw.pause();
+    assert!(pause_while_stopped == Err(Error::AlreadyStopped), "ERROR: pausing a stopped watch did not fail {:?} != AlreadyStopped", pause_while_stopped);
+
+    let unpause_while_stopped = sw.unpause();
+    assert!(unpause_while_stopped == Err(Error::NotPaused), "ERROR: unpausing a stopped watch did not fail {:?} != NotPaused", unpause_while_stopped);
+
+    sw.start().unwrap();
+    sw.restart();
+    assert!(sw.elapsed() >= 0.0, "ERROR: restart left elapsed negative");
+    assert!(sw.get_lap_count() == 0, "ERROR: restart did not clear laps");
+  }
+
+  #[test]
+  //The Duration-based API should agree with the f64-based one
+  fn test_duration_api() {
+    let delay = Duration::new(0, 200_000_000);
+
+    let mut sw = SystemStopwatch::new();
+    sw.start().unwrap();
+    sleep(delay);
+    sw.lap().unwrap();
+    sw.stop().unwrap();
+
+    let lap_duration = sw.lap_duration(0).unwrap();
+    let lap_f64 = sw.get_lap(0).unwrap();
+    assert!((lap_duration.as_secs_f64() - lap_f64).abs() < 1e-9, "ERROR: lap_duration {:?} disagrees with get_lap {}", lap_duration, lap_f64);
+
+    assert!((sw.elapsed_duration().as_secs_f64() - sw.elapsed()).abs() < 1e-9, "ERROR: elapsed_duration disagrees with elapsed");
+    assert!(sw.lap_duration(2).is_none(), "ERROR: lap_duration did not return None for an out-of-range index");
+  }
+
+  #[test]
+  //with_elapsed should seed the accumulator without starting the watch
+  fn test_with_elapsed() {
+    let seed = Duration::new(5, 0);
+    let mut sw = SystemStopwatch::with_elapsed(seed);
+
+    assert!(sw.elapsed_duration() == seed, "ERROR: with_elapsed did not seed the accumulator");
+    assert!(!sw.is_paused(), "ERROR: with_elapsed should not start paused");
+
+    sw.start().unwrap();
+    let elapsed = sw.stop().unwrap();
+    assert!(elapsed >= seed.as_secs_f64(), "ERROR: elapsed {} fell below seeded value {}", elapsed, seed.as_secs_f64());
+  }
+
+  #[test]
+  //A Once timer should finish exactly once and then stay finished
+  fn test_timer_once() {
+    let mut timer = Timer::new(Duration::new(1, 0), TimerMode::Once);
+
+    timer.tick(Duration::new(0, 500_000_000));
+    assert!(!timer.finished(), "ERROR: timer finished early");
+    assert!(!timer.just_finished(), "ERROR: timer reported just_finished early");
+
+    timer.tick(Duration::new(0, 600_000_000));
+    assert!(timer.finished(), "ERROR: timer did not finish after crossing its duration");
+    assert!(timer.just_finished(), "ERROR: timer did not report just_finished on the crossing tick");
+    assert!(timer.times_finished() == 1, "ERROR: times_finished {} != 1", timer.times_finished());
+
+    timer.tick(Duration::new(1, 0));
+    assert!(timer.finished(), "ERROR: Once timer un-finished itself");
+    assert!(!timer.just_finished(), "ERROR: Once timer reported just_finished again on a later tick");
+    assert!(timer.times_finished() == 0, "ERROR: times_finished {} != 0 on a later tick", timer.times_finished());
+  }
+
+  #[test]
+  //A Repeating timer spanning several periods in one tick should wrap and
+  //count every period it crossed
+  fn test_timer_repeating_multi_period() {
+    let mut timer = Timer::new(Duration::new(0, 100_000_000), TimerMode::Repeating);
+
+    timer.tick(Duration::new(0, 350_000_000));
+    assert!(timer.just_finished(), "ERROR: repeating timer did not report just_finished");
+    assert!(timer.times_finished() == 3, "ERROR: times_finished {} != 3", timer.times_finished());
+    assert!(timer.elapsed() == Duration::new(0, 50_000_000), "ERROR: elapsed {:?} did not wrap to the remainder", timer.elapsed());
+
+    //Two consecutive ticks that each complete a period must both report
+    //just_finished (this is the bug Bevy PR #1151 fixed)
+    timer.reset();
+    timer.tick(Duration::new(0, 100_000_000));
+    assert!(timer.just_finished(), "ERROR: first exact-period tick did not report just_finished");
+    timer.tick(Duration::new(0, 100_000_000));
+    assert!(timer.just_finished(), "ERROR: second consecutive exact-period tick did not report just_finished");
+  }
+
+  #[test]
+  //times_finished must not truncate when a tick spans more periods than a u32 can hold
+  fn test_timer_repeating_times_finished_does_not_truncate() {
+    let mut timer = Timer::new(Duration::from_nanos(1), TimerMode::Repeating);
+
+    timer.tick(Duration::new(5, 0));
+    assert!(timer.times_finished() == 5_000_000_000, "ERROR: times_finished {} != 5_000_000_000", timer.times_finished());
+  }
+
+  #[test]
+  //remaining/percent should track progress through the current period
+  fn test_timer_remaining_percent() {
+    let mut timer = Timer::new(Duration::new(2, 0), TimerMode::Once);
+
+    timer.tick(Duration::new(1, 0));
+    assert!(timer.remaining() == Duration::new(1, 0), "ERROR: remaining {:?} != 1s", timer.remaining());
+    assert!((timer.percent() - 0.5).abs() < 1e-6, "ERROR: percent {} != 0.5", timer.percent());
+
+    timer.reset();
+    assert!(timer.elapsed() == Duration::ZERO, "ERROR: reset did not clear elapsed");
+    assert!(!timer.finished(), "ERROR: reset left timer finished");
+  }
+
+  #[test]
+  //Stopwatch should work against a deterministic injected clock, with no
+  //reliance on real sleeps or timing tolerance windows
+  fn test_custom_clock() {
+    let mut sw = Stopwatch::<FakeInstant>::new();
+
+    sw.start().unwrap();
+    advance_fake_clock(500);
+    let lap = sw.lap().unwrap();
+    assert!(lap == 0.5, "ERROR: lap {} != 0.5 on the fake clock", lap);
+
+    advance_fake_clock(250);
+    let elapsed = sw.stop().unwrap();
+    assert!(elapsed == 0.75, "ERROR: elapsed {} != 0.75 on the fake clock", elapsed);
+  }
+
+  #[test]
+  //snapshot() should reconstruct the full start/pause/lap timeline
+  fn test_snapshot() {
+    let mut sw = Stopwatch::<FakeInstant>::new();
+
+    sw.start().unwrap();
+    advance_fake_clock(100);
+    sw.lap().unwrap();
+    advance_fake_clock(100);
+    sw.pause().unwrap();
+    advance_fake_clock(100);
+    sw.unpause().unwrap();
+    advance_fake_clock(100);
+
+    let data = sw.snapshot();
+    assert!(data.start_moments.len() == 2, "ERROR: expected 2 start moments (start + unpause), got {}", data.start_moments.len());
+    assert!(data.pause_moments.len() == 1, "ERROR: expected 1 pause moment, got {}", data.pause_moments.len());
+    assert!(data.lap_moments.len() == 1, "ERROR: expected 1 lap moment, got {}", data.lap_moments.len());
+    assert!(data.laps.len() == 1, "ERROR: expected 1 completed lap, got {}", data.laps.len());
+    assert!(data.laps[0] == Duration::from_millis(100), "ERROR: completed lap {:?} != 100ms", data.laps[0]);
+    assert!(data.lap_elapsed == Duration::from_millis(200), "ERROR: in-progress lap_elapsed {:?} != 200ms", data.lap_elapsed);
+    assert!(data.elapsed == Duration::from_millis(300), "ERROR: total elapsed {:?} != 300ms", data.elapsed);
+  }
+
+  #[test]
+  //guard() should stop the watch automatically when it goes out of scope
+  fn test_guard_stops_on_drop() {
+    let mut sw = Stopwatch::<FakeInstant>::new();
+
+    {
+      let _g = sw.guard().unwrap();
+      advance_fake_clock(100);
     }
 
-    let elapsed = sw.stop();
+    assert!(!sw.is_paused(), "ERROR: stopwatch left paused after guard dropped");
+    let second_start = sw.start();
+    assert!(second_start.is_ok(), "ERROR: stopwatch was not stopped by the guard: {:?}", second_start);
+    assert!(sw.stop().unwrap() == 0.1, "ERROR: guard did not fold the guarded interval into elapsed");
+  }
 
-    println!("Runtime: {}", elapsed);
+  #[test]
+  //the guarded interval must be folded in even if the scope panics
+  fn test_guard_stops_on_panic() {
+    let mut sw = Stopwatch::<FakeInstant>::new();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      let _g = sw.guard().unwrap();
+      advance_fake_clock(100);
+      panic!("boom");
+    }));
+    assert!(result.is_err(), "ERROR: expected the closure to panic");
+
+    assert!(sw.start().is_ok(), "ERROR: stopwatch was not stopped by the guard during unwinding");
+    assert!(sw.stop().unwrap() == 0.1, "ERROR: guard did not fold the guarded interval in during unwinding");
+  }
 }
-